@@ -1,4 +1,4 @@
-use super::{error::StatFuncError, Result};
+use super::{error::StatFuncError, log1mexp, Result};
 
 use libm::lgamma;
 
@@ -52,15 +52,37 @@ pub fn beta(p: f64, q: f64) -> Result<f64> {
 ///   is None then the value is calculated.  Any supplied value is assumed
 ///   to be correct.
 ///
-///   Output, the value of the incomplete Beta function ratio.
-pub fn betain(p: f64, q: f64, x: f64, lnbeta: Option<f64>) -> Result<f64> {
+///   Input, log_p, if true the natural logarithm of the incomplete Beta
+///   function ratio is returned instead, computed without round-tripping
+///   through `exp`/`ln` so it stays accurate where the ratio itself would
+///   underflow.
+///
+///   Output, the value of the incomplete Beta function ratio (or its
+///   logarithm, if log_p is set).
+// Above this combined magnitude the AS63 Soper series needs too many terms
+// to converge (and loses accuracy through cancellation), so we switch to
+// the continued fraction expansion below instead.
+const CF_THRESHOLD: f64 = 150.0;
+
+pub fn betain(p: f64, q: f64, x: f64, lnbeta: Option<f64>, log_p: bool) -> Result<f64> {
     check_beta_params(p, q)?;
     if !(0.0..=1.0).contains(&x) {
         Err(StatFuncError::InvalidProbability)
     } else if x == 0.0 || x == 1.0 {
-        Ok(x)
+        Ok(if log_p { x.ln() } else { x })
     } else {
         let lnbeta = lnbeta.unwrap_or_else(|| _lbeta(p, q));
+        // Above this combined magnitude the AS63 Soper series needs too many terms
+        // to converge (and loses accuracy through cancellation), so we switch to
+        // the continued fraction expansion below instead.
+        const CF_THRESHOLD: f64 = 150.0;
+        if p + q > CF_THRESHOLD {
+            return Ok(if log_p {
+                _betain_cf_log(p, q, x, lnbeta)
+            } else {
+                _betain_cf(p, q, x, lnbeta)
+            });
+        }
         let accuracy = 1.0e-14;
 
         // Change tail if necessary
@@ -84,10 +106,6 @@ pub fn betain(p: f64, q: f64, x: f64, lnbeta: Option<f64>) -> Result<f64> {
             temp = term.abs();
 
             if temp <= accuracy && temp <= accuracy * value {
-                value *= (pp * xx.ln() + (qq - 1.0) * cx.ln() - lnbeta).exp() / pp;
-                if flip {
-                    value = 1.0 - value
-                }
                 break;
             }
 
@@ -105,8 +123,148 @@ pub fn betain(p: f64, q: f64, x: f64, lnbeta: Option<f64>) -> Result<f64> {
                 }
             }
         }
-        Ok(value)
+
+        let log_prefactor = pp * xx.ln() + (qq - 1.0) * cx.ln() - lnbeta - pp.ln();
+        if log_p {
+            let log_value = value.ln() + log_prefactor;
+            Ok(if flip { log1mexp(log_value) } else { log_value })
+        } else {
+            let mut value = value * log_prefactor.exp();
+            if flip {
+                value = 1.0 - value
+            }
+            Ok(value)
+        }
+    }
+}
+
+/// Evaluates `I_x(a, b)` with the modified Lentz continued fraction, used
+/// by [`betain`] in place of the AS63 Soper series once `p + q` grows large
+/// enough that the series converges too slowly (see external doc 12, the
+/// OpenOffice incomplete-beta interpreter patch).
+///
+/// `lnbeta` is the precomputed `ln(B(a, b))`, which is symmetric in `a`
+/// and `b` so it can be reused across the reflection below.
+fn _betain_cf(a: f64, b: f64, x: f64, lnbeta: f64) -> f64 {
+    // Converges faster on the other side of the midpoint, so reflect.
+    if x >= (a + 1.0) / (a + b + 2.0) {
+        return 1.0 - _betain_cf(b, a, 1.0 - x, lnbeta);
+    }
+    let h = _betain_cf_raw(a, b, x);
+    (a * x.ln() + b * (1.0 - x).ln() - lnbeta).exp() * h / a
+}
+
+/// Log-space counterpart of [`_betain_cf`], used by `betain` when
+/// `log_p` is set.
+fn _betain_cf_log(a: f64, b: f64, x: f64, lnbeta: f64) -> f64 {
+    if x >= (a + 1.0) / (a + b + 2.0) {
+        return log1mexp(_betain_cf_log(b, a, 1.0 - x, lnbeta));
+    }
+    let h = _betain_cf_raw(a, b, x);
+    a * x.ln() + b * (1.0 - x).ln() - lnbeta - a.ln() + h.ln()
+}
+
+/// Evaluates the modified Lentz continued fraction itself (the `cf` factor
+/// in `I_x(a,b) = x^a (1-x)^b / (a*B(a,b)) * cf`), without the prefactor
+/// or the midpoint reflection, so it can feed both the linear- and
+/// log-space combination in [`_betain_cf`]/[`_betain_cf_log`].
+fn _betain_cf_raw(a: f64, b: f64, x: f64) -> f64 {
+    const TINY: f64 = 1.0e-30;
+    let qab = a + b;
+    let mut c = 1.0;
+    let mut d = 1.0 - qab * x / (a + 1.0);
+    if d.abs() < TINY {
+        d = TINY;
+    }
+    d = 1.0 / d;
+    let mut h = d;
+
+    let mut m = 1.0_f64;
+    loop {
+        let d_even = m * (b - m) * x / ((a + 2.0 * m - 1.0) * (a + 2.0 * m));
+        c = 1.0 + d_even / c;
+        if c.abs() < TINY {
+            c = TINY;
+        }
+        d = 1.0 + d_even * d;
+        if d.abs() < TINY {
+            d = TINY;
+        }
+        d = 1.0 / d;
+        h *= c * d;
+
+        let d_odd = -(a + m) * (qab + m) * x / ((a + 2.0 * m) * (a + 2.0 * m + 1.0));
+        c = 1.0 + d_odd / c;
+        if c.abs() < TINY {
+            c = TINY;
+        }
+        d = 1.0 + d_odd * d;
+        if d.abs() < TINY {
+            d = TINY;
+        }
+        d = 1.0 / d;
+        let del = c * d;
+        h *= del;
+
+        if (del - 1.0).abs() < 1.0e-15 {
+            break;
+        }
+        m += 1.0;
+    }
+    h
+}
+
+/// Computes the `x` satisfying `betain(p, q, x) == prob`, i.e. the inverse
+/// of the incomplete Beta function ratio.
+///
+/// `lnbeta`, the logarithm of the complete Beta function, is handled the
+/// same way as in [`betain`]: pass `None` to have it computed, or a cached
+/// value if the caller already has one.
+///
+/// The root is bracketed in `(0, 1)` and refined with Newton-Raphson using
+/// the incomplete-beta density as the derivative, falling back to
+/// bisection whenever a Newton step would leave the current bracket.
+pub fn qbeta(p: f64, q: f64, prob: f64, lnbeta: Option<f64>) -> Result<f64> {
+    check_beta_params(p, q)?;
+    if !(0.0..=1.0).contains(&prob) {
+        Err(StatFuncError::InvalidProbability)
+    } else if prob == 0.0 || prob == 1.0 {
+        Ok(prob)
+    } else {
+        let lnbeta = lnbeta.unwrap_or_else(|| _lbeta(p, q));
+        Ok(_qbeta(p, q, prob, lnbeta))
+    }
+}
+
+fn _qbeta(p: f64, q: f64, prob: f64, lnbeta: f64) -> f64 {
+    const MAX_ITER: usize = 100;
+    const ACCURACY: f64 = 1.0e-12;
+
+    let mut lo = 0.0_f64;
+    let mut hi = 1.0_f64;
+    let mut x = p / (p + q);
+
+    for _ in 0..MAX_ITER {
+        let f = betain(p, q, x, Some(lnbeta), false).unwrap() - prob;
+        if f > 0.0 {
+            hi = x;
+        } else {
+            lo = x;
+        }
+        if f.abs() < ACCURACY {
+            break;
+        }
+
+        // Newton step using the incomplete-beta density x^(p-1)(1-x)^(q-1)/B(p,q).
+        let log_density = (p - 1.0) * x.ln() + (q - 1.0) * (1.0 - x).ln() - lnbeta;
+        let x_new = x - f / log_density.exp();
+        x = if x_new.is_finite() && x_new > lo && x_new < hi {
+            x_new
+        } else {
+            0.5 * (lo + hi)
+        };
     }
+    x
 }
 
 fn check_beta_params(alpha: f64, beta: f64) -> Result<()> {
@@ -117,12 +275,13 @@ fn check_beta_params(alpha: f64, beta: f64) -> Result<()> {
     }
 }
 
+#[cfg(test)]
 macro_rules! beta_inc {
     ($a:expr, $b:expr, $c:expr) => {
-        betain($a, $b, $c, None)
+        betain($a, $b, $c, None, false)
     };
     ($a:expr, $b:expr, $c:expr, $beta:expr) => {
-        betain($a, $b, $c, Some($beta))
+        betain($a, $b, $c, Some($beta), false)
     };
 }
 
@@ -138,19 +297,19 @@ mod tests {
 
     #[test]
     fn betain_test() {
-        let z = betain(4.0, 5.0, 0.75, None).expect("Error in betain()");
+        let z = betain(4.0, 5.0, 0.75, None, false).expect("Error in betain()");
         assert!((z - 0.9727020263671875).abs() < 1.0e-12)
     }
 
     #[test]
     fn betain_test1() {
-        let z = betain(20.0, 5.0, 0.1, None).expect("Error in betain()");
+        let z = betain(20.0, 5.0, 0.1, None, false).expect("Error in betain()");
         assert!((z - 7.1215255e-17).abs() < 1.0e-12)
     }
 
     #[test]
     fn betain_test2() {
-        let z = betain(20.0, 5.0, 0.9, None).expect("Error in betain()");
+        let z = betain(20.0, 5.0, 0.9, None, false).expect("Error in betain()");
         assert!((z - 0.914_925_114_121_329_2).abs() < 1.0e-12)
     }
 
@@ -160,6 +319,42 @@ mod tests {
         assert!((z - 0.914_925_114_121_329_2).abs() < 1.0e-12)
     }
 
+    #[test]
+    fn betain_cf_test() {
+        let z = betain(2000.0, 5.0, 0.99, None, false).expect("Error in betain()");
+        assert!((z - 1.536_316_747_481_957e-5).abs() < 1.0e-12)
+    }
+
+    #[test]
+    fn betain_cf_test1() {
+        let z = betain(5.0, 2000.0, 0.01, None, false).expect("Error in betain()");
+        assert!((z - 0.9999846368325252).abs() < 1.0e-12)
+    }
+
+    #[test]
+    fn betain_log_p_test() {
+        let z = betain(20.0, 5.0, 0.1, None, true).expect("Error in betain()");
+        assert!((z - 7.1215255e-17_f64.ln()).abs() < 1.0e-9)
+    }
+
+    #[test]
+    fn betain_cf_log_p_test() {
+        let z = betain(2000.0, 5.0, 0.99, None, true).expect("Error in betain()");
+        assert!((z - (-11.083_537_635_695_74_f64)).abs() < 1.0e-9)
+    }
+
+    #[test]
+    fn qbeta_test() {
+        let z = qbeta(4.0, 5.0, 0.9727020263671875, None).expect("Error in qbeta()");
+        assert!((z - 0.75).abs() < 1.0e-10)
+    }
+
+    #[test]
+    fn qbeta_test1() {
+        let z = qbeta(20.0, 5.0, 0.9149251141213292, None).expect("Error in qbeta()");
+        assert!((z - 0.9).abs() < 1.0e-10)
+    }
+
     #[test]
     fn beta_inc1() {
         let z = beta_inc!(20.0, 5.0, 0.9, lbeta(20.0, 5.0).unwrap()).expect("Error in betain()");
@@ -8,4 +8,8 @@ pub enum StatFuncError {
     InvalidProbability,
     #[error("Invalid degrees of freedom parameter for Students-t distribution (must be > 0)")]
     InvalidStudentsTParameter,
+    #[error("Invalid parameters for incomplete gamma function (a must be >0, x must be >=0")]
+    InvalidGammaParameters,
+    #[error("Invalid degrees of freedom parameter for chi-squared distribution (must be > 0)")]
+    InvalidChiSquaredParameter,
 }
@@ -0,0 +1,66 @@
+/// Performs a one-sample Kolmogorov-Smirnov goodness-of-fit test of
+/// `samples` against the continuous distribution with CDF `cdf` (e.g. a
+/// closure built from [`crate::pnorm::pnorm`] or [`crate::students_t::StudentsT::pt`]).
+///
+/// Returns the KS `D` statistic and an asymptotic p-value derived from the
+/// Kolmogorov distribution survival function.
+pub fn ks_test(samples: &[f64], cdf: impl Fn(f64) -> f64) -> (f64, f64) {
+    let n = samples.len();
+    if n == 0 {
+        return (0.0, 1.0);
+    }
+    let n_f = n as f64;
+
+    let mut sorted = samples.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    let d = sorted.iter().enumerate().fold(0.0_f64, |d, (i, &x)| {
+        let f = cdf(x);
+        let i_f = (i + 1) as f64;
+        d.max((f - i_f / n_f).abs()).max((f - (i_f - 1.0) / n_f).abs())
+    });
+
+    let lambda = (n_f.sqrt() + 0.12 + 0.11 / n_f.sqrt()) * d;
+    (d, ks_prob(lambda))
+}
+
+/// Asymptotic survival function of the Kolmogorov distribution,
+/// `Q(lambda) = 2 sum_{k>=1} (-1)^(k-1) exp(-2 k^2 lambda^2)`.
+fn ks_prob(lambda: f64) -> f64 {
+    let lambda_sq = lambda * lambda;
+    let mut sum = 0.0_f64;
+    let mut sign = 1.0_f64;
+    let mut k = 1.0_f64;
+    loop {
+        let term = sign * (-2.0 * k * k * lambda_sq).exp();
+        sum += term;
+        if term.abs() < 1.0e-10 {
+            break;
+        }
+        sign = -sign;
+        k += 1.0;
+    }
+    (2.0 * sum).clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pnorm::pnorm;
+
+    #[test]
+    fn ks_test_uniform() {
+        let samples: Vec<f64> = (1..=20).map(|i| i as f64 / 21.0).collect();
+        let (d, p) = ks_test(&samples, |x| x);
+        assert!(d < 0.05);
+        assert!(p > 0.9);
+    }
+
+    #[test]
+    fn ks_test_bad_fit() {
+        let samples = vec![5.0; 20];
+        let (d, p) = ks_test(&samples, |x| pnorm(x, true, false));
+        assert!(d > 0.9);
+        assert!(p < 1.0e-10);
+    }
+}
@@ -1,6 +1,19 @@
 pub mod beta;
 pub mod error;
+pub mod gamma;
+pub mod ks;
 pub mod pnorm;
+pub mod sample;
 pub mod students_t;
 
 pub type Result<T> = std::result::Result<T, error::StatFuncError>;
+
+/// Numerically stable `ln(1 - exp(x))` for `x <= 0`, shared by the
+/// `log_p` variants of the CDFs in this crate.
+pub(crate) fn log1mexp(x: f64) -> f64 {
+    if x > -std::f64::consts::LN_2 {
+        (-x.exp_m1()).ln()
+    } else {
+        (-x.exp()).ln_1p()
+    }
+}
@@ -1,23 +1,37 @@
 use libm::ldexp;
 
-pub fn pnorm(z: f64, lower_tail: bool) -> f64 {
+use crate::log1mexp;
+
+/// `log_p` returns `ln(p)` directly rather than rounding through
+/// `p.ln()`, so it stays accurate in the tails where `p` itself
+/// underflows (e.g. `pnorm(-40.0, true, true)`).
+pub fn pnorm(z: f64, lower_tail: bool, log_p: bool) -> f64 {
     if z.is_infinite() {
-        match (z.is_sign_negative(), lower_tail) {
+        let p: f64 = match (z.is_sign_negative(), lower_tail) {
             (true, true) => 0.0,   // -inf, lower tail
             (true, false) => 1.0,  // -inf, upper tail
             (false, true) => 1.0,  // +inf, lower tail
             (false, false) => 0.0, // +inf, upper tail
+        };
+        if log_p {
+            p.ln()
+        } else {
+            p
         }
     } else if z.is_nan() {
         z
     } else {
-        _pnorm(z, lower_tail)
+        _pnorm(z, lower_tail, log_p)
     }
 }
 
 const M_SQRT_32: f64 = 5.656_854_249_492_381;
 const M_1_SQRT_2PI: f64 = 0.398_942_280_401_432_7;
-fn _pnorm(x: f64, lower_tail: bool) -> f64 {
+// The coefficient tables below are transcribed verbatim from the Cody
+// rational-approximation algorithm this function implements; truncating
+// their digits would make them harder to check against the reference.
+#[allow(clippy::excessive_precision)]
+fn _pnorm(x: f64, lower_tail: bool, log_p: bool) -> f64 {
     const A: f64 = 0.065682337918207449113;
     const AB: [(f64, f64); 4] = [
         (2.2352520354606839287, 47.20258190468824187),
@@ -46,17 +60,23 @@ fn _pnorm(x: f64, lower_tail: bool) -> f64 {
     ];
     const EPS: f64 = f64::EPSILON * 0.5;
 
-    let do_del = |x, temp| -> f64 {
-        let xsq = unsafe { ldexp(ldexp(x, 4).trunc(), -4) };
+    // Returns `ln(exp(-xsq^2/2 - del/2) * temp)` without exponentiating
+    // first, so the asymptotic tail expansions stay accurate in log space.
+    let do_del_log = |x, temp: f64| -> f64 {
+        let xsq = ldexp(ldexp(x, 4).trunc(), -4);
         let del = (x - xsq) * (x + xsq);
-        (-xsq * xsq / 2.0 - del / 2.0).exp() * temp
+        -xsq * xsq / 2.0 - del / 2.0 + temp.ln()
     };
 
-    let swap_tail = |x, p: f64| match (x < 0.0, lower_tail) {
-        (true, true) => p,
-        (true, false) => 1.0 - p,
-        (false, true) => 1.0 - p,
-        (false, false) => p,
+    let swap_tail = |x, p: f64| -> f64 {
+        let same_tail = matches!((x < 0.0, lower_tail), (true, true) | (false, false));
+        if same_tail {
+            p
+        } else if log_p {
+            log1mexp(p)
+        } else {
+            1.0 - p
+        }
     };
     let y = x.abs();
     if y <= 0.67448975 {
@@ -70,10 +90,11 @@ fn _pnorm(x: f64, lower_tail: bool) -> f64 {
         };
         let (a, b) = unsafe { AB.get_unchecked(3) };
         let temp = x * (xnum + a) / (xden + b);
-        if lower_tail {
-            0.5 + temp
+        let p = if lower_tail { 0.5 + temp } else { 0.5 - temp };
+        if log_p {
+            p.ln()
         } else {
-            0.5 - temp
+            p
         }
     } else if y <= M_SQRT_32 {
         let (xnum, xden) = CD[..7].iter().fold((C * y, y), |(num, den), (c, d)| {
@@ -81,8 +102,14 @@ fn _pnorm(x: f64, lower_tail: bool) -> f64 {
         });
         let (c, d) = unsafe { CD.get_unchecked(7) };
         let temp = (xnum + c) / (xden + d);
-        swap_tail(x, do_del(y, temp))
-    } else if (lower_tail && (-37.5193..8.2924).contains(&x))
+        let log_del = do_del_log(y, temp);
+        swap_tail(x, if log_p { log_del } else { log_del.exp() })
+    } else if log_p
+        // The `x` range below is where the *linear* probability is still
+        // representable before it underflows to 0.0; `do_del_log` never
+        // exponentiates, so `log_p` callers need the asymptotic expansion
+        // past that cutoff too (e.g. `pnorm(-40.0, true, true)`).
+        || (lower_tail && (-37.5193..8.2924).contains(&x))
         || (!lower_tail && (-8.2924..37.5193).contains(&x))
     {
         let xsq = x.powi(-2);
@@ -91,9 +118,131 @@ fn _pnorm(x: f64, lower_tail: bool) -> f64 {
         });
         let (p, q) = unsafe { PQ.get_unchecked(4) };
         let temp = (M_1_SQRT_2PI - xsq * (xnum + p) / (xden + q)) / y;
-        swap_tail(x, do_del(x, temp))
+        let log_del = do_del_log(x, temp);
+        swap_tail(x, if log_p { log_del } else { log_del.exp() })
+    } else {
+        swap_tail(x, if log_p { f64::NEG_INFINITY } else { 0.0 })
+    }
+}
+
+/// Inverse of [`pnorm`]: given a probability `p`, returns the `z` with
+/// `pnorm(z, lower_tail, false) == p`, using Wichura's AS241 rational
+/// approximation (accurate to full double precision, matching `pnorm`).
+///
+/// `p` must lie in `[0, 1]`; `p == 0`/`p == 1` return `-inf`/`+inf`
+/// (swapped when `lower_tail` is false) and values outside `[0, 1]`
+/// return `NaN`.
+///
+/// When `lower_tail` is false, `p` is first replaced by `1 - p`, so very
+/// small upper-tail probabilities lose precision in that subtraction, and
+/// those for which `1 - p` rounds to `1.0` return `+inf`.
+// The coefficient tables below are transcribed verbatim from AS241;
+// truncating their digits would make them harder to check against the
+// reference.
+#[allow(clippy::excessive_precision)]
+pub fn qnorm(p: f64, lower_tail: bool) -> f64 {
+    if p.is_nan() || !(0.0..=1.0).contains(&p) {
+        return f64::NAN;
+    }
+    let p = if lower_tail { p } else { 1.0 - p };
+    if p == 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    if p == 1.0 {
+        return f64::INFINITY;
+    }
+
+    let q = p - 0.5;
+    if q.abs() <= 0.425 {
+        let r = 0.180625 - q * q;
+        let num = ((((((r * 2509.0809287301226727 + 33430.575583588128105) * r
+            + 67265.770927008700853)
+            * r
+            + 45921.953931549871457)
+            * r
+            + 13731.693765509461125)
+            * r
+            + 1971.5909503065514427)
+            * r
+            + 133.14166789178437745)
+            * r
+            + 3.387132872796366608;
+        let den = ((((((r * 5226.495278852854561 + 28729.085735721942674) * r
+            + 39307.89580009271061)
+            * r
+            + 21213.794301586595867)
+            * r
+            + 5394.1960214247511077)
+            * r
+            + 687.1870074920579083)
+            * r
+            + 42.313330701600911252)
+            * r
+            + 1.0;
+        q * num / den
     } else {
-        swap_tail(x, 0.0)
+        let mut r = if q < 0.0 { p } else { 1.0 - p };
+        r = (-r.ln()).sqrt();
+        let val = if r <= 5.0 {
+            r -= 1.6;
+            let num = ((((((r * 7.7454501427834140764e-4 + 0.0227238449892691845833) * r
+                + 0.24178072517745061177)
+                * r
+                + 1.27045825245236838258)
+                * r
+                + 3.64784832476320460504)
+                * r
+                + 5.7694972214606914055)
+                * r
+                + 4.6303378461565452959)
+                * r
+                + 1.42343711074968357734;
+            let den = ((((((r * 1.05075007164441684324e-9 + 5.475938084995344946e-4) * r
+                + 0.0151986665636164571966)
+                * r
+                + 0.14810397642748007459)
+                * r
+                + 0.68976733498510000455)
+                * r
+                + 1.6763848301838038494)
+                * r
+                + 2.05319162663775882187)
+                * r
+                + 1.0;
+            num / den
+        } else {
+            r -= 5.0;
+            let num = ((((((r * 2.01033439929228813265e-7 + 2.71155556874348757815e-5) * r
+                + 0.0012426609473880784386)
+                * r
+                + 0.026532189526576123093)
+                * r
+                + 0.29656057182850489123)
+                * r
+                + 1.7848265399172913358)
+                * r
+                + 5.4637849111641143699)
+                * r
+                + 6.6579046435011037772;
+            let den = ((((((r * 2.04426310338993978564e-15 + 1.4215117583164458887e-7) * r
+                + 1.8463183175100546818e-5)
+                * r
+                + 7.868691311456132591e-4)
+                * r
+                + 0.0148753612908506148525)
+                * r
+                + 0.13692988092273580531)
+                * r
+                + 0.59983220655588793769)
+                * r
+                + 1.0;
+            num / den
+        };
+        if q < 0.0 {
+            -val
+        } else {
+            val
+        }
     }
 }
 
@@ -103,50 +252,89 @@ mod tests {
 
     #[test]
     fn test1() {
-        let p = pnorm(0.25, true);
+        let p = pnorm(0.25, true, false);
         assert!((p - 0.5987063256829237).abs() < 1.0e-12)
     }
     #[test]
     fn test2() {
-        let p = pnorm(-0.125, true);
+        let p = pnorm(-0.125, true, false);
         eprintln!("{p}");
         assert!((p - 0.45026177516988714).abs() < 1.0e-12)
     }
     #[test]
     fn test3() {
-        let p = pnorm(-0.125, false);
+        let p = pnorm(-0.125, false, false);
         eprintln!("{p}");
         assert!((p - 0.5497382248301129).abs() < 1.0e-12)
     }
     #[test]
     fn test4() {
-        let p = pnorm(1.96, true);
+        let p = pnorm(1.96, true, false);
         eprintln!("{p}");
         assert!((p - 0.9750021048517796).abs() < 1.0e-12)
     }
     #[test]
     fn test5() {
-        let p = pnorm(-3.0, false);
+        let p = pnorm(-3.0, false, false);
         eprintln!("{p}");
         assert!((p - 0.9986501019683699).abs() < 1.0e-12)
     }
     #[test]
     fn test6() {
-        let p = pnorm(-25.0, true);
+        let p = pnorm(-25.0, true, false);
         eprintln!("{:e}", p.ln());
         assert!((p.ln() - -3.1663940800802027e2).abs() < 1.0e-12)
     }
     #[test]
     fn test7() {
-        let p = pnorm(25.0, false);
+        let p = pnorm(25.0, false, false);
         eprintln!("{:e}", p.ln());
         assert!((p.ln() - -3.1663940800802027e2).abs() < 1.0e-12)
     }
 
     #[test]
     fn test8() {
-        let p = pnorm(25.0, true);
+        let p = pnorm(25.0, true, false);
         eprintln!("{:e}", p);
         assert!(1.0 - p < 1.0e-12)
     }
+
+    #[test]
+    fn pnorm_log_p_test() {
+        // Far enough into the tail that the probability itself underflows
+        // to 0.0, so only the log_p path carries any information.
+        let p = pnorm(-40.0, true, true);
+        assert!((p + 804.6084420137538).abs() < 1.0e-9)
+    }
+
+    #[test]
+    fn pnorm_log_p_matches_ln_test() {
+        let log_p = pnorm(-3.0, false, true);
+        let p = pnorm(-3.0, false, false);
+        assert!((log_p - p.ln()).abs() < 1.0e-12)
+    }
+
+    #[test]
+    fn qnorm_test1() {
+        let z = qnorm(0.9750021048517796, true);
+        assert!((z - 1.96).abs() < 1.0e-9)
+    }
+
+    #[test]
+    fn qnorm_test2() {
+        let z = qnorm(0.5987063256829237, true);
+        assert!((z - 0.25).abs() < 1.0e-9)
+    }
+
+    #[test]
+    fn qnorm_test3() {
+        let z = qnorm(0.9986501019683699, false);
+        assert!((z + 3.0).abs() < 1.0e-9)
+    }
+
+    #[test]
+    fn qnorm_tail_test() {
+        let z = qnorm(1.0e-10, true);
+        assert!((z + 6.361340902404056).abs() < 1.0e-9)
+    }
 }
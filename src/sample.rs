@@ -0,0 +1,78 @@
+use rand::Rng;
+
+/// Types that can draw a random variate from themselves given an
+/// external source of randomness.
+///
+/// Mirrors the split the `rv` crate made between `Rv`'s density duties
+/// (`HasDensity`) and its sampling duties (`Sampleable`): this crate
+/// already has `dt`/[`crate::students_t::StudentsT::dt`] for densities,
+/// so `Sampleable` is the sampling half of that surface.
+pub trait Sampleable {
+    fn sample<R: Rng>(&self, rng: &mut R) -> f64;
+}
+
+/// Standard normal distribution, provided purely as a [`Sampleable`]
+/// wrapper — `pnorm`/`qnorm` already cover CDF and quantile duties
+/// for N(0,1) as free functions in [`crate::pnorm`].
+pub struct Normal;
+
+impl Sampleable for Normal {
+    fn sample<R: Rng>(&self, rng: &mut R) -> f64 {
+        standard_normal(rng)
+    }
+}
+
+/// Box-Muller transform for a single standard normal variate.
+pub(crate) fn standard_normal<R: Rng>(rng: &mut R) -> f64 {
+    let u1: f64 = rng.gen::<f64>().max(f64::MIN_POSITIVE);
+    let u2: f64 = rng.gen();
+    (-2.0 * u1.ln()).sqrt() * (std::f64::consts::TAU * u2).cos()
+}
+
+/// Marsaglia-Tsang sampler for a standard Gamma(shape, 1) variate
+/// (`shape` must be `> 0`); used by [`chi_squared_sample`] below.
+fn standard_gamma<R: Rng>(rng: &mut R, shape: f64) -> f64 {
+    if shape < 1.0 {
+        let u: f64 = rng.gen();
+        standard_gamma(rng, shape + 1.0) * u.powf(1.0 / shape)
+    } else {
+        let d = shape - 1.0 / 3.0;
+        let c = 1.0 / (9.0 * d).sqrt();
+        loop {
+            let (x, mut v) = loop {
+                let x = standard_normal(rng);
+                let v = 1.0 + c * x;
+                if v > 0.0 {
+                    break (x, v);
+                }
+            };
+            v = v * v * v;
+            let u: f64 = rng.gen();
+            if u < 1.0 - 0.0331 * x * x * x * x || u.ln() < 0.5 * x * x + d * (1.0 - v + v.ln()) {
+                return d * v;
+            }
+        }
+    }
+}
+
+/// Draws a Chi-squared(`k`) variate as `2 * Gamma(k/2, 1)`, used by
+/// [`crate::students_t::StudentsT`]'s [`Sampleable`] impl.
+pub(crate) fn chi_squared_sample<R: Rng>(rng: &mut R, k: f64) -> f64 {
+    2.0 * standard_gamma(rng, 0.5 * k)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ks::ks_test, pnorm::pnorm};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn normal_sample_ks_test() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let samples: Vec<f64> = (0..1000).map(|_| Normal.sample(&mut rng)).collect();
+        let (d, p) = ks_test(&samples, |x| pnorm(x, true, false));
+        assert!(d < 0.1);
+        assert!(p > 0.05);
+    }
+}
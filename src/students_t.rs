@@ -1,10 +1,15 @@
 use super::{
-    beta::{betain, lbeta},
+    beta::{betain, lbeta, qbeta},
     Result,
 };
 use libm::lgamma;
+use rand::Rng;
 
-use crate::error::StatFuncError;
+use crate::{
+    error::StatFuncError,
+    log1mexp,
+    sample::{chi_squared_sample, standard_normal, Sampleable},
+};
 
 pub struct StudentsT {
     v: f64,
@@ -28,8 +33,23 @@ impl StudentsT {
         _lstudents_t_pdf(t, self.v, self.konst)
     }
 
-    pub fn pt(&self, t: f64) -> f64 {
-        _students_t_cdf(t, self.v, Some(self.lnbeta))
+    pub fn pt(&self, t: f64, log_p: bool) -> f64 {
+        _students_t_cdf(t, self.v, Some(self.lnbeta), log_p)
+    }
+
+    pub fn qt(&self, prob: f64) -> Result<f64> {
+        _students_t_qt(prob, self.v, Some(self.lnbeta))
+    }
+}
+
+impl Sampleable for StudentsT {
+    /// Draws `Z / sqrt(C / v)` for independent `Z ~ N(0, 1)` and
+    /// `C ~ ChiSquared(v)`, the standard normal-variance-mixture
+    /// construction of a Student's-t variate.
+    fn sample<R: Rng>(&self, rng: &mut R) -> f64 {
+        let z = standard_normal(rng);
+        let c = chi_squared_sample(rng, self.v);
+        z / (c / self.v).sqrt()
     }
 }
 
@@ -52,29 +72,61 @@ fn _students_t_pdf(t: f64, v: f64, konst: f64) -> f64 {
 }
 
 #[inline]
-fn _students_t_cdf(t: f64, v: f64, lnbeta: Option<f64>) -> f64 {
+fn _students_t_cdf(t: f64, v: f64, lnbeta: Option<f64>, log_p: bool) -> f64 {
     let x = v / (v + t * t);
-    let z = 0.5 * betain(0.5 * v, 0.5, x, lnbeta).unwrap();
-    if t < 0.0 {
-        z
+    let z = betain(0.5 * v, 0.5, x, lnbeta, log_p).unwrap();
+    if log_p {
+        let log_z = z - std::f64::consts::LN_2;
+        if t < 0.0 {
+            log_z
+        } else {
+            log1mexp(log_z)
+        }
     } else {
-        1.0 - z
+        let z = 0.5 * z;
+        if t < 0.0 {
+            z
+        } else {
+            1.0 - z
+        }
     }
 }
 
-fn ldt(t: f64, v: f64) -> Result<f64> {
+/// Inverts the `x = v / (v + t^2)` substitution used by
+/// [`_students_t_cdf`] to turn a quantile in `(0, 1)` for the incomplete
+/// beta function back into a Student's-t quantile.
+fn _students_t_qt(prob: f64, v: f64, lnbeta: Option<f64>) -> Result<f64> {
+    if !(0.0..=1.0).contains(&prob) {
+        return Err(StatFuncError::InvalidProbability);
+    }
+    let (p2, neg) = if prob < 0.5 {
+        (2.0 * prob, true)
+    } else {
+        (2.0 * (1.0 - prob), false)
+    };
+    let x = qbeta(0.5 * v, 0.5, p2, lnbeta)?;
+    let t = (v * (1.0 - x) / x).sqrt();
+    Ok(if neg { -t } else { t })
+}
+
+pub fn ldt(t: f64, v: f64) -> Result<f64> {
     check_students_t_param(v)?;
     let konst = lgamma(0.5 * (v + 1.0)) - lgamma(0.5 * v) - 0.5 * (v * std::f64::consts::PI).ln();
     Ok(_lstudents_t_pdf(t, v, konst))
 }
 
-fn dt(t: f64, v: f64) -> Result<f64> {
+pub fn dt(t: f64, v: f64) -> Result<f64> {
     ldt(t, v).map(|z| z.exp())
 }
 
-fn pt(t: f64, v: f64) -> Result<f64> {
+pub fn pt(t: f64, v: f64, log_p: bool) -> Result<f64> {
     check_students_t_param(v)?;
-    Ok(_students_t_cdf(t, v, None))
+    Ok(_students_t_cdf(t, v, None, log_p))
+}
+
+pub fn qt(prob: f64, v: f64) -> Result<f64> {
+    check_students_t_param(v)?;
+    _students_t_qt(prob, v, None)
 }
 
 #[cfg(test)]
@@ -116,15 +168,54 @@ mod tests {
 
     #[test]
     fn pt_test() {
-        let z = pt(2.5, 2.8).unwrap();
+        let z = pt(2.5, 2.8, false).unwrap();
         assert!((z - 0.953134106244337).abs() < 1.0e-12)
     }
 
     #[test]
     fn pt_test1() {
         let s = StudentsT::new(2.8).unwrap();
-        let z = s.pt(-3.4);
+        let z = s.pt(-3.4, false);
         println!("{z}");
         assert!((z - 0.02355410567174815).abs() < 1.0e-12)
     }
+
+    #[test]
+    fn pt_log_test() {
+        let z = pt(2.5, 2.8, true).unwrap();
+        assert!((z - 0.953134106244337_f64.ln()).abs() < 1.0e-9)
+    }
+
+    #[test]
+    fn pt_log_test1() {
+        let s = StudentsT::new(2.8).unwrap();
+        let z = s.pt(-3.4, true);
+        assert!((z - 0.02355410567174815_f64.ln()).abs() < 1.0e-9)
+    }
+
+    #[test]
+    fn qt_test() {
+        let z = qt(0.953134106244337, 2.8).unwrap();
+        assert!((z - 2.5).abs() < 1.0e-9)
+    }
+
+    #[test]
+    fn qt_test1() {
+        let s = StudentsT::new(2.8).unwrap();
+        let z = s.qt(0.02355410567174815).unwrap();
+        assert!((z + 3.4).abs() < 1.0e-9)
+    }
+
+    #[test]
+    fn sample_ks_test() {
+        use crate::ks::ks_test;
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let s = StudentsT::new(5.0).unwrap();
+        let mut rng = StdRng::seed_from_u64(42);
+        let samples: Vec<f64> = (0..1000).map(|_| s.sample(&mut rng)).collect();
+        let (d, p) = ks_test(&samples, |x| s.pt(x, false));
+        assert!(d < 0.1);
+        assert!(p > 0.05);
+    }
 }